@@ -1,7 +1,19 @@
+use crossbeam_channel::{Receiver, Sender};
 use imagedata::ImageData;
+pub use imagedata::BayerPattern;
 use std::any::Any;
 use std::{fmt::Display, time::Duration};
 
+/// Pixel format a camera is capturing in, reported by
+/// [`CameraInfo::get_image_type`] and set via [`CameraUnit::set_image_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageType {
+    Raw8,
+    Raw16,
+    Rgb24,
+    Y8,
+}
+
 #[derive(Clone, Copy)]
 pub struct ROI {
     pub x_min: i32,
@@ -22,6 +34,67 @@ impl Display for ROI {
     }
 }
 
+/// Sensor readout mirroring, reported by [`CameraInfo::get_flip`] and set via
+/// [`CameraUnit::set_flip`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlipStatus {
+    None,
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+/// A running frame-streaming session returned by [`CameraUnit::start_stream`].
+///
+/// Frames flow from the backend's capture thread to the consumer over an
+/// internal bounded channel; returning a frame's [`ImageData`] via
+/// [`Self::release`] lets the backend recycle its buffer for the next
+/// capture instead of reallocating. While a `FrameStream` is alive,
+/// implementations are expected to report [`CameraInfo::is_capturing`] as
+/// `true`.
+pub struct FrameStream {
+    receiver: Receiver<ImageData>,
+    free: Sender<ImageData>,
+}
+
+impl FrameStream {
+    /// Wrap the filled/free channel pair a backend's worker thread
+    /// communicates over into a `FrameStream` handle.
+    pub fn new(receiver: Receiver<ImageData>, free: Sender<ImageData>) -> Self {
+        Self { receiver, free }
+    }
+
+    /// Block until the next frame is available, or the stream is stopped.
+    pub fn recv(&self) -> Result<ImageData, Error> {
+        self.receiver
+            .recv()
+            .map_err(|_| Error::Message("Frame stream closed".to_string()))
+    }
+
+    /// Return a frame to the backend's free pool so its buffer can be
+    /// reused by a later capture. Dropping the frame instead of releasing it
+    /// just forces the next capture to allocate a new one.
+    pub fn release(&self, frame: ImageData) {
+        self.free.send(frame).ok();
+    }
+}
+
+/// A generic, introspectable description of a single camera control,
+/// returned by [`CameraUnit::list_controls`].
+#[derive(Debug, Clone)]
+pub struct Control {
+    /// Backend-specific identifier passed to [`CameraUnit::get_control`] and
+    /// [`CameraUnit::set_control`].
+    pub control_type: String,
+    pub name: String,
+    pub description: String,
+    pub min: i64,
+    pub max: i64,
+    pub default: i64,
+    pub can_auto: bool,
+    pub is_writable: bool,
+}
+
 pub trait CameraInfo {
     fn camera_ready(&self) -> bool {
         false
@@ -66,6 +139,20 @@ pub trait CameraInfo {
     fn get_pixel_size(&self) -> Option<f32> {
         None
     }
+
+    fn get_image_type(&self) -> ImageType {
+        ImageType::Raw8
+    }
+
+    /// Bayer (CFA) mosaic pattern of the sensor, or `None` for a monochrome
+    /// camera or an already-debayered image type.
+    fn get_bayer_pattern(&self) -> Option<BayerPattern> {
+        None
+    }
+
+    fn get_flip(&self) -> FlipStatus {
+        FlipStatus::None
+    }
 }
 
 pub trait CameraUnit : CameraInfo {
@@ -89,6 +176,18 @@ pub trait CameraUnit : CameraInfo {
         None
     }
 
+    /// Start a continuous streaming capture, backed by a worker thread that
+    /// fills a bounded pool of up to `pool_size` frames in the background.
+    fn start_stream(&mut self, _pool_size: usize) -> Result<FrameStream, Error> {
+        Err(Error::Message("Not implemented".to_string()))
+    }
+
+    /// Stop a streaming capture started by [`Self::start_stream`], joining
+    /// its worker thread.
+    fn stop_stream(&mut self) -> Result<(), Error> {
+        Err(Error::Message("Not implemented".to_string()))
+    }
+
     fn set_exposure(&mut self, _exposure: Duration) -> Result<Duration, Error> {
         Err(Error::Message("Not implemented".to_string()))
     }
@@ -149,6 +248,19 @@ pub trait CameraUnit : CameraInfo {
         Err(Error::Message("Not implemented".to_string()))
     }
 
+    /// Switch the camera's output pixel format. Returns the format actually
+    /// applied, which may differ from `image_type` if the camera only
+    /// approximates it.
+    fn set_image_type(&mut self, _image_type: ImageType) -> Result<ImageType, Error> {
+        Err(Error::Message("Not implemented".to_string()))
+    }
+
+    /// Mirror the sensor readout horizontally, vertically, or both. Returns
+    /// the flip state actually applied.
+    fn set_flip(&mut self, _flip: FlipStatus) -> Result<FlipStatus, Error> {
+        Err(Error::Message("Not implemented".to_string()))
+    }
+
     fn get_bin_x(&self) -> i32 {
         1
     }
@@ -171,6 +283,25 @@ pub trait CameraUnit : CameraInfo {
     fn get_status(&self) -> String {
         "Not implemented".to_string()
     }
+
+    /// Enumerate the controls this camera exposes, along with their valid
+    /// range and writability.
+    fn list_controls(&self) -> Vec<Control> {
+        Vec::new()
+    }
+
+    /// Read a control's current value and whether it is under automatic
+    /// control, by its [`Control::control_type`] identifier.
+    fn get_control(&self, id: &str) -> Result<(i64, bool), Error> {
+        Err(Error::InvalidControlType(id.to_string()))
+    }
+
+    /// Write a control's value, by its [`Control::control_type`] identifier,
+    /// optionally handing it over to automatic control. Returns the value
+    /// actually applied.
+    fn set_control(&mut self, id: &str, _value: i64, _auto: bool) -> Result<i64, Error> {
+        Err(Error::InvalidControlType(id.to_string()))
+    }
 }
 
 #[derive(Debug)]
@@ -195,6 +326,7 @@ pub enum Error {
     ExposureFailed(String),
     InvalidValue(String),
     OutOfBounds(String),
+    VideoModeActive,
 }
 
 impl Display for Error {
@@ -220,7 +352,57 @@ impl Display for Error {
             Error::ExposureFailed(msg) => msg.clone(),
             Error::InvalidValue(msg) => msg.clone(),
             Error::OutOfBounds(msg) => msg.clone(),
+            Error::VideoModeActive => "Video mode active".to_string(),
         };
         write!(f, "{}", msg)
     }
+}
+
+impl std::error::Error for Error {}
+
+impl Error {
+    /// Map an SDK numeric result code to an `Error`, or `Ok(())` for the
+    /// success code `0`. Variants that normally carry SDK-supplied context
+    /// (an index, a path, a message, ...) are constructed with a
+    /// placeholder, since that context isn't recoverable from the code
+    /// alone.
+    pub fn from_sdk_code(code: i32) -> Result<(), Error> {
+        match code {
+            0 => Ok(()),
+            1 => Err(Error::InvalidIndex(0)),
+            2 => Err(Error::InvalidId(0)),
+            3 => Err(Error::InvalidControlType(String::new())),
+            4 => Err(Error::CameraClosed),
+            5 => Err(Error::CameraRemoved),
+            6 => Err(Error::InvalidPath(String::new())),
+            7 => Err(Error::InvalidFormat(String::new())),
+            8 => Err(Error::InvalidSize(0)),
+            9 => Err(Error::InvalidImageType(String::new())),
+            10 => Err(Error::OutOfBounds(String::new())),
+            11 => Err(Error::TimedOut),
+            12 => Err(Error::InvalidSequence),
+            13 => Err(Error::BufferTooSmall(0)),
+            14 => Err(Error::VideoModeActive),
+            15 => Err(Error::ExposureInProgress),
+            16 => Err(Error::GeneralError("General error".to_string())),
+            _ => Err(Error::GeneralError(format!(
+                "Unknown SDK error code: {}",
+                code
+            ))),
+        }
+    }
+}
+
+impl TryFrom<i32> for Error {
+    type Error = ();
+
+    /// Map a nonzero SDK error code to an `Error`. Fails with `Err(())` for
+    /// the success code `0`, since that isn't an error to represent; use
+    /// [`Error::from_sdk_code`] to handle both cases at once.
+    fn try_from(code: i32) -> Result<Self, Self::Error> {
+        match Error::from_sdk_code(code) {
+            Ok(()) => Err(()),
+            Err(e) => Ok(e),
+        }
+    }
 }
\ No newline at end of file
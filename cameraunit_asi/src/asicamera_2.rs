@@ -7,24 +7,43 @@ mod asicamera2_bindings;
 use asicamera2_bindings::*;
 
 use std::{
+    collections::HashMap,
     ffi::{c_long, c_uchar, CStr},
     fmt::Display,
     mem::MaybeUninit,
-    sync::{Arc, Mutex},
-    thread::sleep,
-    time::{Duration, SystemTime},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread::{sleep, JoinHandle},
+    time::{Duration, Instant, SystemTime},
 };
 
-use cameraunit::{CameraInfo, CameraUnit, Error, ROI};
+use cameraunit::{CameraInfo, CameraUnit, Control, Error, FlipStatus, FrameStream, ImageType, ROI};
+use crossbeam_channel::{bounded, Receiver, Sender};
 use image::DynamicImage;
-use imagedata::{ImageData, ImageMetaData};
+use imagedata::{BayerPattern, ImageData, ImageMetaData};
 use log::{info, warn};
 
+/// Number of pre-allocated raw frame buffers the video capture thread
+/// recycles, and the capacity of its ready-frame queue.
+const VIDEO_POOL_SIZE: usize = 4;
+
+/// Consolidated snapshot of the cooler/TEC thermal subsystem.
+#[derive(Debug, Clone, Copy)]
+pub struct ThermalStatus {
+    pub current_temp: f32,
+    pub target_temp: f32,
+    pub cooler_power: f32,
+    pub settled: bool,
+}
+
 pub struct CameraUnit_ASI {
     id: Arc<ASICamId>,
     capturing: Arc<Mutex<bool>>,
     props: Box<ASICameraProps>,
-    // control_caps: Vec<ASIControlCaps>,
+    control_caps: HashMap<ASIControlType, ControlCaps>,
     gain_min: i64,
     gain_max: i64,
     exp_min: Duration,
@@ -33,6 +52,7 @@ pub struct CameraUnit_ASI {
     is_dark_frame: bool,
     image_fmt: ASIImageFormat,
     roi: ROI,
+    video_stream: Option<VideoStream>,
 }
 
 #[derive(Clone)]
@@ -45,6 +65,7 @@ pub struct CameraInfo_ASI {
     width: u32,
     psize: f64,
     is_cooler_cam: bool,
+    bayer_pattern: Option<ASIBayerPattern>,
 }
 
 #[derive(Clone)]
@@ -183,7 +204,7 @@ pub fn open_camera(id: i32) -> Result<(CameraUnit_ASI, CameraInfo_ASI), Error> {
             id: Arc::new(ASICamId(prop.id)),
             capturing: Arc::new(Mutex::new(false)),
             props: Box::new(prop.clone()),
-            // control_caps: ccaps,
+            control_caps: ccaps.iter().map(|c| (c.id, ControlCaps::from(c))).collect(),
             gain_min: gain_min,
             gain_max: gain_max,
             exp_min: exp_min,
@@ -210,6 +231,7 @@ pub fn open_camera(id: i32) -> Result<(CameraUnit_ASI, CameraInfo_ASI), Error> {
                 bin_x: 1,
                 bin_y: 1,
             },
+            video_stream: None,
         };
 
         cobj.set_start_pos(0, 0)?;
@@ -229,6 +251,7 @@ pub fn open_camera(id: i32) -> Result<(CameraUnit_ASI, CameraInfo_ASI), Error> {
             width: prop.max_width as u32,
             psize: prop.pixel_size,
             is_cooler_cam: prop.is_cooler_cam,
+            bayer_pattern: prop.bayer_pattern,
         };
 
         return Ok((cobj, cinfo));
@@ -305,7 +328,7 @@ impl CameraUnit_ASI {
         if self.is_capturing() {
             return Err(Error::ExposureInProgress);
         }
-        let mut roi = self.get_roi_format()?;
+        let mut roi = get_roi_format(self.id.0)?;
         roi.fmt = fmt;
         self.set_roi_format(&roi)?;
         self.image_fmt = fmt;
@@ -316,34 +339,364 @@ impl CameraUnit_ASI {
         &self.props
     }
 
-    fn get_roi_format(&self) -> Result<ASIRoiMode, Error> {
-        let mut roi = ASIRoiMode {
-            width: 0,
-            height: 0,
-            bin: 0,
-            fmt: ASIImageFormat::Image_RAW8,
-        };
-        let mut fmt: i32 = 0;
-        let res = unsafe {
-            ASIGetROIFormat(
-                self.id.0,
-                &mut roi.width,
-                &mut roi.height,
-                &mut roi.bin,
-                &mut fmt,
-            )
-        };
+    /// Enumerate every control the camera reports, keyed by its [`ASIControlType`].
+    pub fn get_control_caps(&self) -> &HashMap<ASIControlType, ControlCaps> {
+        &self.control_caps
+    }
+
+    /// Read the current value and auto-mode flag for a given control.
+    pub fn get_control_value(&self, ctyp: ASIControlType) -> Result<(i64, bool), Error> {
+        if !self.control_caps.contains_key(&ctyp) {
+            return Err(Error::InvalidControlType(format!("{:?}", ctyp)));
+        }
+        let (val, is_auto) = get_control_value(self.id.0, ctyp)?;
+        Ok((val as i64, is_auto))
+    }
+
+    /// Write a value (and optionally enable the SDK's auto mode) for a given control.
+    pub fn set_control_value(&self, ctyp: ASIControlType, value: i64, auto: bool) -> Result<(), Error> {
+        let cap = self
+            .control_caps
+            .get(&ctyp)
+            .ok_or_else(|| Error::InvalidControlType(format!("{:?}", ctyp)))?;
+        if !cap.is_writable {
+            return Err(Error::InvalidControlType(format!(
+                "Control {:?} is not writable",
+                ctyp
+            )));
+        }
+        if auto && !cap.is_auto_supported {
+            return Err(Error::InvalidControlType(format!(
+                "Control {:?} does not support auto mode",
+                ctyp
+            )));
+        }
+        if value < cap.min || value > cap.max {
+            return Err(Error::InvalidValue(format!(
+                "Value {} for control {:?} is outside of range {} - {}",
+                value, ctyp, cap.min, cap.max
+            )));
+        }
+        set_control_value(self.id.0, ctyp, value as c_long, auto)
+    }
+
+    /// List every control the camera reports, keyed by [`ASIControlType`],
+    /// for applications that want the typed ASI API rather than
+    /// [`CameraUnit::list_controls`]'s backend-agnostic `&str` keying.
+    pub fn list_control_caps(&self) -> Vec<ControlCaps> {
+        self.control_caps.values().cloned().collect()
+    }
+
+    /// Look up the [`ASIControlType`] whose SDK-reported name is `id`, for
+    /// the [`CameraUnit`]'s `&str`-keyed control API.
+    fn find_control_type(&self, id: &str) -> Option<ASIControlType> {
+        self.control_caps
+            .values()
+            .find(|cap| cap.name == id)
+            .map(|cap| cap.control_type)
+    }
+
+    /// Start continuous streaming capture and hand back a [`VideoStream`]
+    /// that yields frames as they arrive.
+    ///
+    /// Fails with [`Error::ExposureInProgress`] if a single-shot
+    /// [`CameraUnit::capture_image`] is already underway, mirroring the
+    /// SDK's own `ASI_ERROR_VIDEO_MODE_ACTIVE`/`ASI_ERROR_EXPOSURE_IN_PROGRESS`
+    /// mutual exclusion.
+    pub fn start_video_capture(&self) -> Result<VideoStream, Error> {
+        let mut capturing = self.capturing.lock().unwrap();
+        if *capturing {
+            return Err(Error::ExposureInProgress);
+        }
+
+        let roi = get_roi_format(self.id.0)?;
+        let frame_size = video_frame_size(&roi);
+
+        let res = unsafe { ASIStartVideoCapture(self.id.0) };
         if res == ASI_ERROR_CODE_ASI_ERROR_INVALID_ID as i32 {
             return Err(Error::InvalidId(self.id.0));
         } else if res == ASI_ERROR_CODE_ASI_ERROR_CAMERA_CLOSED as i32 {
             return Err(Error::CameraClosed);
+        } else if res == ASI_ERROR_CODE_ASI_ERROR_EXPOSURE_IN_PROGRESS as i32 {
+            return Err(Error::ExposureInProgress);
         }
-        if let Some(fmt) = ASIImageFormat::from_u32(fmt as u32) {
-            roi.fmt = fmt;
-            return Ok(roi);
-        } else {
-            return Err(Error::InvalidMode(format!("Invalid image format: {}", fmt)));
+        *capturing = true;
+        drop(capturing);
+
+        let (ready_tx, ready_rx) = bounded::<ImageData>(VIDEO_POOL_SIZE);
+        let (free_tx, free_rx) = bounded::<Vec<u8>>(VIDEO_POOL_SIZE);
+        for _ in 0..VIDEO_POOL_SIZE {
+            free_tx.send(vec![0u8; frame_size]).ok();
+        }
+        let free_sender = free_tx.clone();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_thr = stop.clone();
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let dropped_thr = dropped.clone();
+        let id = self.id.clone();
+        let capturing_thr = self.capturing.clone();
+        let exposure = self.exposure;
+        let bin_x = self.get_bin_x() as u32;
+        let bin_y = self.get_bin_y() as u32;
+        let img_top = self.roi.y_min as u32;
+        let img_left = self.roi.x_min as u32;
+        let camera_name = self.camera_name().to_string();
+        let gain = self.get_gain_raw();
+        let offset = self.get_offset() as i64;
+        let gain_min = self.gain_min as i32;
+        let gain_max = self.gain_max as i32;
+        let bayer_pattern = self
+            .props
+            .bayer_pattern
+            .filter(|_| roi.fmt != ASIImageFormat::Image_RGB24)
+            .map(BayerPattern::from);
+
+        let handle = std::thread::spawn(move || {
+            while !stop_thr.load(Ordering::Relaxed) {
+                let mut buf = match free_rx.recv_timeout(Duration::from_millis(500)) {
+                    Ok(buf) => buf,
+                    Err(_) => continue,
+                };
+                let wait_ms = (exposure.as_millis() as i32 * 2).max(500);
+                let res = unsafe {
+                    ASIGetVideoData(
+                        id.0,
+                        buf.as_mut_ptr() as *mut c_uchar,
+                        buf.len() as c_long,
+                        wait_ms,
+                    )
+                };
+                if res != 0 {
+                    free_tx.send(buf).ok();
+                    continue;
+                }
+                let start_time = SystemTime::now();
+                let img = match roi.fmt {
+                    ASIImageFormat::Image_RAW8 => {
+                        let mut out = DynamicImage::new_luma8(roi.width as u32, roi.height as u32)
+                            .into_luma8();
+                        out.copy_from_slice(&buf);
+                        DynamicImage::from(out)
+                    }
+                    ASIImageFormat::Image_RAW16 => {
+                        let data: Vec<u16> = buf
+                            .chunks_exact(2)
+                            .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+                            .collect();
+                        let mut out =
+                            DynamicImage::new_luma16(roi.width as u32, roi.height as u32)
+                                .into_luma16();
+                        out.copy_from_slice(&data);
+                        DynamicImage::from(out)
+                    }
+                    ASIImageFormat::Image_RGB24 => {
+                        let mut out = DynamicImage::new_rgb8(roi.width as u32, roi.height as u32)
+                            .into_rgb8();
+                        out.copy_from_slice(&buf);
+                        DynamicImage::from(out)
+                    }
+                };
+                free_tx.send(buf).ok();
+
+                let mut meta = ImageMetaData::full_builder(
+                    bin_x,
+                    bin_y,
+                    img_top,
+                    img_left,
+                    get_temperature(id.0).unwrap_or(-273.0),
+                    exposure,
+                    start_time,
+                    &camera_name,
+                    gain,
+                    offset,
+                    gain_min,
+                    gain_max,
+                );
+                meta.bayer_pattern = bayer_pattern;
+                match ready_tx.try_send(ImageData::new(img, meta)) {
+                    Ok(()) => {}
+                    Err(crossbeam_channel::TrySendError::Full(_)) => {
+                        // Consumer isn't keeping up with the ready queue; drop
+                        // this frame rather than blocking the acquisition loop.
+                        dropped_thr.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(crossbeam_channel::TrySendError::Disconnected(_)) => break,
+                }
+            }
+            let res = unsafe { ASIStopVideoCapture(id.0) };
+            if res == ASI_ERROR_CODE_ASI_ERROR_INVALID_ID as i32 {
+                warn!("Invalid camera ID: {}", id.0);
+            }
+            *capturing_thr.lock().unwrap() = false;
+        });
+
+        Ok(VideoStream {
+            stop,
+            dropped,
+            handle: Some(handle),
+            receiver: ready_rx,
+            _free_sender: free_sender,
+        })
+    }
+
+    /// Drive the hardware auto-exposure/auto-gain loop: write the AGC limit
+    /// controls (`AutoExpTgtBrightness`, `AutoExpMaxExp`, `AutoExpMaxGain`)
+    /// and then enable (or disable) the `auto` flag on the `Exposure` and
+    /// `Gain` controls.
+    ///
+    /// Rejects the change with [`Error::ExposureInProgress`] while a
+    /// single-shot capture is underway.
+    pub fn set_auto_exposure(
+        &self,
+        enabled: bool,
+        target_brightness: i64,
+        max_exposure: Duration,
+        max_gain: i64,
+    ) -> Result<(), Error> {
+        let capturing = self.capturing.lock().unwrap();
+        if *capturing {
+            return Err(Error::ExposureInProgress);
+        }
+        drop(capturing);
+
+        self.set_control_value(
+            ASIControlType::AutoExpTgtBrightness,
+            target_brightness,
+            false,
+        )?;
+        self.set_control_value(
+            ASIControlType::AutoExpMaxExp,
+            max_exposure.as_millis() as i64,
+            false,
+        )?;
+        self.set_control_value(ASIControlType::AutoExpMaxGain, max_gain, false)?;
+
+        let (exp_val, _) = get_control_value(self.id.0, ASIControlType::Exposure)?;
+        self.set_control_value(ASIControlType::Exposure, exp_val as i64, enabled)?;
+        let (gain_val, _) = get_control_value(self.id.0, ASIControlType::Gain)?;
+        self.set_control_value(ASIControlType::Gain, gain_val as i64, enabled)?;
+        Ok(())
+    }
+
+    /// Whether the hardware auto-exposure loop is currently enabled, and the
+    /// exposure value it last settled on.
+    pub fn get_exposure_auto(&self) -> Result<(Duration, bool), Error> {
+        let (val, is_auto) = get_control_value(self.id.0, ASIControlType::Exposure)?;
+        Ok((Duration::from_micros(val as u64), is_auto))
+    }
+
+    /// Whether the hardware auto-gain loop is currently enabled, and the gain
+    /// value it last settled on.
+    pub fn get_gain_auto(&self) -> Result<(i64, bool), Error> {
+        let (val, is_auto) = get_control_value(self.id.0, ASIControlType::Gain)?;
+        Ok((val as i64, is_auto))
+    }
+
+    /// Demosaic a Bayer-mosaiced (RAW8/RAW16) frame into RGB via
+    /// [`ImageData::debayer`], using the Bayer pattern recorded in its
+    /// metadata at capture time.
+    ///
+    /// Binned frames (`bin_x`/`bin_y` > 1) are returned unchanged since the
+    /// sensor may combine pixels of different colors when binning, leaving
+    /// the frame effectively mono.
+    pub fn debayer(&self, data: &ImageData) -> Result<ImageData, Error> {
+        if self.roi.bin_x > 1 || self.roi.bin_y > 1 {
+            return Ok(data.clone());
         }
+        let rgb = data.debayer().map_err(Error::InvalidMode)?;
+        Ok(ImageData::new(rgb, data.get_metadata().clone()))
+    }
+
+    /// Turn the TEC cooler on or off. Requires [`ASICameraProps::is_cooler_cam`].
+    pub fn set_cooler_on(&self, on: bool) -> Result<(), Error> {
+        self.guard_cooler_cam()?;
+        self.set_control_value(ASIControlType::CoolerOn, if on { 1 } else { 0 }, false)
+    }
+
+    /// Turn the case fan on or off. Requires [`ASICameraProps::is_cooler_cam`].
+    pub fn set_fan_on(&self, on: bool) -> Result<(), Error> {
+        self.guard_cooler_cam()?;
+        self.set_control_value(ASIControlType::FanOn, if on { 1 } else { 0 }, false)
+    }
+
+    /// Turn the anti-dew heater on or off. Requires [`ASICameraProps::is_cooler_cam`].
+    pub fn set_anti_dew_heater(&self, on: bool) -> Result<(), Error> {
+        self.guard_cooler_cam()?;
+        self.set_control_value(ASIControlType::AntiDewHeater, if on { 1 } else { 0 }, false)
+    }
+
+    fn guard_cooler_cam(&self) -> Result<(), Error> {
+        if !self.props.is_cooler_cam {
+            return Err(Error::InvalidControlType(
+                "Camera does not have cooler".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Read a consolidated snapshot of the thermal subsystem: current sensor
+    /// temperature, cooler duty cycle, the given `target_temp`, and whether
+    /// the loop has settled on it within `tolerance` degrees C.
+    pub fn get_thermal_status(&self, target_temp: f32, tolerance: f32) -> ThermalStatus {
+        let current_temp = self.get_temperature().unwrap_or(f32::NAN);
+        ThermalStatus {
+            current_temp,
+            target_temp,
+            cooler_power: self.get_cooler_power().unwrap_or(0.0),
+            settled: (current_temp - target_temp).abs() <= tolerance,
+        }
+    }
+
+    /// Poll [`Self::get_thermal_status`] every `poll_interval` until the
+    /// sensor has settled on `target_temp` within `tolerance` degrees C, or
+    /// `timeout` elapses. Useful to wait out TEC stabilization before a
+    /// science exposure begins.
+    pub fn wait_for_thermal_stable(
+        &self,
+        target_temp: f32,
+        tolerance: f32,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<ThermalStatus, Error> {
+        let start = Instant::now();
+        loop {
+            let status = self.get_thermal_status(target_temp, tolerance);
+            if status.settled {
+                return Ok(status);
+            }
+            if start.elapsed() > timeout {
+                return Err(Error::TimedOut);
+            }
+            sleep(poll_interval);
+        }
+    }
+
+    /// Capture a single exposure and demosaic it using [`Self::debayer`].
+    pub fn capture_image_debayered(&self) -> Result<ImageData, Error> {
+        let data = self.capture_image()?;
+        self.debayer(&data)
+    }
+
+    /// Capture a single exposure and return a `scale_factor`-downscaled
+    /// preview of it, via [`ImageData::downscale`].
+    ///
+    /// The preview is derived from the same exposure as a full-resolution
+    /// capture would be (only one exposure is taken), so it always reflects
+    /// what a simultaneous full-resolution frame would show.
+    pub fn capture_preview(&self, scale_factor: u32) -> Result<ImageData, Error> {
+        if scale_factor == 0 {
+            return Err(Error::InvalidValue(
+                "scale_factor must be at least 1".to_owned(),
+            ));
+        }
+        let full = self.capture_image()?;
+        if scale_factor == 1 {
+            return Ok(full);
+        }
+        let mut preview = full.downscale(scale_factor);
+        preview.add_extended_attrib("PREVIEW_SCALE", &format!("{}", scale_factor));
+        Ok(preview)
     }
 
     fn set_roi_format(&self, roi: &ASIRoiMode) -> Result<(), Error> {
@@ -447,6 +800,25 @@ impl CameraInfo for CameraInfo_ASI {
     fn set_temperature(&self, temperature: f32) -> Result<f32, Error> {
         set_temperature(self.id.0, temperature, self.is_cooler_cam)
     }
+
+    fn get_image_type(&self) -> ImageType {
+        get_roi_format(self.id.0)
+            .map(|roi| roi.fmt.into())
+            .unwrap_or(ImageType::Raw8)
+    }
+
+    fn get_bayer_pattern(&self) -> Option<BayerPattern> {
+        if self.get_image_type() == ImageType::Rgb24 {
+            return None;
+        }
+        self.bayer_pattern.map(BayerPattern::from)
+    }
+
+    fn get_flip(&self) -> FlipStatus {
+        get_control_value(self.id.0, ASIControlType::Flip)
+            .map(|(val, _)| flip_status_from_raw(val))
+            .unwrap_or(FlipStatus::None)
+    }
 }
 
 impl CameraInfo for CameraUnit_ASI {
@@ -493,6 +865,23 @@ impl CameraInfo for CameraUnit_ASI {
     fn set_temperature(&self, temperature: f32) -> Result<f32, Error> {
         set_temperature(self.id.0, temperature, self.props.is_cooler_cam)
     }
+
+    fn get_image_type(&self) -> ImageType {
+        self.image_fmt.into()
+    }
+
+    fn get_bayer_pattern(&self) -> Option<BayerPattern> {
+        if self.get_image_type() == ImageType::Rgb24 {
+            return None;
+        }
+        self.props.bayer_pattern.map(BayerPattern::from)
+    }
+
+    fn get_flip(&self) -> FlipStatus {
+        get_control_value(self.id.0, ASIControlType::Flip)
+            .map(|(val, _)| flip_status_from_raw(val))
+            .unwrap_or(FlipStatus::None)
+    }
 }
 
 impl CameraUnit for CameraUnit_ASI {
@@ -514,6 +903,31 @@ impl CameraUnit for CameraUnit_ASI {
         Ok(())
     }
 
+    /// Start continuous streaming via [`Self::start_video_capture`] and hand
+    /// the frames out through the generic [`FrameStream`] API. `pool_size`
+    /// is accepted for trait-object parity with other backends but the ASI
+    /// video pipeline always pre-allocates its own fixed-size buffer pool;
+    /// frames released back through `FrameStream::release` are simply
+    /// dropped, since the video worker already recycles its own raw frame
+    /// buffers.
+    fn start_stream(&mut self, pool_size: usize) -> Result<FrameStream, Error> {
+        let stream = self.start_video_capture()?;
+        let receiver = stream.receiver.clone();
+        self.video_stream = Some(stream);
+        let (free_tx, _free_rx) = bounded::<ImageData>(pool_size.max(1));
+        Ok(FrameStream::new(receiver, free_tx))
+    }
+
+    fn stop_stream(&mut self) -> Result<(), Error> {
+        match self.video_stream.take() {
+            Some(stream) => {
+                stream.stop();
+                Ok(())
+            }
+            None => Err(Error::Message("No stream is currently running".to_owned())),
+        }
+    }
+
     fn get_min_exposure(&self) -> Result<Duration, Error> {
         Ok(self.exp_min)
     }
@@ -543,7 +957,7 @@ impl CameraUnit for CameraUnit_ASI {
                 return Err(Error::ExposureFailed("Unknown".to_owned()));
             }
             *capturing = false;
-            roi = self.get_roi_format()?;
+            roi = get_roi_format(self.id.0)?;
             *capturing = true;
             start_time = SystemTime::now();
             let res = unsafe {
@@ -676,13 +1090,17 @@ impl CameraUnit for CameraUnit_ASI {
                     DynamicImage::from(img)
                 }
             };
+            let exposure = self
+                .get_exposure_auto()
+                .map(|(exp, _)| exp)
+                .unwrap_or(self.exposure);
             let mut meta = ImageMetaData::full_builder(
                 self.get_bin_x() as u32,
                 self.get_bin_y() as u32,
                 self.roi.y_min as u32,
                 self.roi.x_min as u32,
                 self.get_temperature().unwrap_or(-273.0),
-                self.exposure,
+                exposure,
                 start_time,
                 self.camera_name(),
                 self.get_gain_raw(),
@@ -701,6 +1119,36 @@ impl CameraUnit for CameraUnit_ASI {
                     }
                 ),
             );
+            meta.add_extended_attrib(
+                "DARKFRAME",
+                if self.is_dark_frame { "T" } else { "F" },
+            );
+            meta.add_extended_attrib("EGAIN", &format!("{}", self.props.e_per_adu));
+            meta.add_extended_attrib("XPIXSZ", &format!("{}", self.props.pixel_size));
+            if let Some(power) = self.get_cooler_power() {
+                meta.add_extended_attrib("COOLERPW", &format!("{}", power));
+            }
+            if let Some(pattern) = self
+                .props
+                .bayer_pattern
+                .filter(|_| roi.fmt != ASIImageFormat::Image_RGB24)
+            {
+                let pattern = BayerPattern::from(pattern);
+                meta.bayer_pattern = Some(pattern);
+                meta.add_extended_attrib("BAYERPAT", pattern.as_fits_str());
+            }
+
+            // The driver's auto-exposure/auto-gain loop may have adjusted these
+            // since the exposure/gain was requested; `meta.exposure`/`meta.gain`
+            // above already reflect the readback, but mirror them into
+            // extended attributes too since those are only written when auto
+            // mode is actually on.
+            if let Ok((exp, true)) = get_control_value(self.id.0, ASIControlType::Exposure) {
+                meta.add_extended_attrib("AUTO_EXPOSURE_US", &format!("{}", exp));
+            }
+            if let Ok((gain, true)) = get_control_value(self.id.0, ASIControlType::Gain) {
+                meta.add_extended_attrib("AUTO_GAIN", &format!("{}", gain));
+            }
 
             return Ok(ImageData::new(img, meta));
         }
@@ -893,7 +1341,7 @@ impl CameraUnit for CameraUnit_ASI {
             return Err(Error::ExposureInProgress);
         }
 
-        let mut roi_md = self.get_roi_format()?;
+        let mut roi_md = get_roi_format(self.id.0)?;
         let (xs, ys) = self.get_start_pos()?;
         let roi_md_old = roi_md.clone();
 
@@ -929,6 +1377,51 @@ impl CameraUnit for CameraUnit_ASI {
         self.is_dark_frame = !open;
         Ok(open)
     }
+
+    /// Mirror the sensor readout via the SDK's `Flip` control.
+    fn set_flip(&mut self, flip: FlipStatus) -> Result<FlipStatus, Error> {
+        let capturing = self.capturing.lock().unwrap();
+        if *capturing {
+            return Err(Error::ExposureInProgress);
+        }
+        self.set_control_value(ASIControlType::Flip, flip_status_to_raw(flip), false)?;
+        Ok(self.get_flip())
+    }
+
+    /// Enumerate the camera's controls, keyed by the SDK-reported name also
+    /// accepted by [`Self::get_control`]/[`Self::set_control`]. Equivalent
+    /// to [`CameraUnit_ASI::list_control_caps`] (the [`ASIControlType`]-keyed inherent
+    /// method), reshaped into the generic [`Control`] representation.
+    fn list_controls(&self) -> Vec<Control> {
+        self.control_caps
+            .values()
+            .map(|cap| Control {
+                control_type: cap.name.clone(),
+                name: cap.name.clone(),
+                description: cap.description.clone(),
+                min: cap.min,
+                max: cap.max,
+                default: cap.default,
+                can_auto: cap.is_auto_supported,
+                is_writable: cap.is_writable,
+            })
+            .collect()
+    }
+
+    fn get_control(&self, id: &str) -> Result<(i64, bool), Error> {
+        let ctyp = self
+            .find_control_type(id)
+            .ok_or_else(|| Error::InvalidControlType(id.to_string()))?;
+        self.get_control_value(ctyp)
+    }
+
+    fn set_control(&mut self, id: &str, value: i64, auto: bool) -> Result<i64, Error> {
+        let ctyp = self
+            .find_control_type(id)
+            .ok_or_else(|| Error::InvalidControlType(id.to_string()))?;
+        self.set_control_value(ctyp, value, auto)?;
+        Ok(self.get_control_value(ctyp)?.0)
+    }
 }
 
 impl Default for ASIControlCaps {
@@ -1069,6 +1562,17 @@ impl ASIBayerPattern {
     }
 }
 
+impl From<ASIBayerPattern> for BayerPattern {
+    fn from(pattern: ASIBayerPattern) -> Self {
+        match pattern {
+            ASIBayerPattern::Bayer_RG => BayerPattern::RGGB,
+            ASIBayerPattern::Bayer_BG => BayerPattern::BGGR,
+            ASIBayerPattern::Bayer_GR => BayerPattern::GRBG,
+            ASIBayerPattern::Bayer_GB => BayerPattern::GBRG,
+        }
+    }
+}
+
 impl ASIImageFormat {
     fn from_u32(val: u32) -> Option<Self> {
         match val as i32 {
@@ -1080,6 +1584,16 @@ impl ASIImageFormat {
     }
 }
 
+impl From<ASIImageFormat> for ImageType {
+    fn from(fmt: ASIImageFormat) -> Self {
+        match fmt {
+            ASIImageFormat::Image_RAW8 => ImageType::Raw8,
+            ASIImageFormat::Image_RAW16 => ImageType::Raw16,
+            ASIImageFormat::Image_RGB24 => ImageType::Rgb24,
+        }
+    }
+}
+
 #[repr(u32)]
 #[derive(Debug, PartialEq, Clone, Copy)]
 enum ASIBayerPattern {
@@ -1098,8 +1612,8 @@ pub enum ASIImageFormat {
 }
 
 #[repr(i32)]
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum ASIControlType {
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ASIControlType {
     Gain = ASI_CONTROL_TYPE_ASI_GAIN as i32,
     Exposure = ASI_CONTROL_TYPE_ASI_EXPOSURE as i32,
     Gamma = ASI_CONTROL_TYPE_ASI_GAMMA as i32,
@@ -1145,6 +1659,39 @@ struct ASIControlCaps {
     is_writable: bool,
 }
 
+/// Describes a single camera control (gain, exposure, gamma, white balance, ...)
+/// as reported by the SDK, together with its allowed range and capabilities.
+#[derive(Clone)]
+pub struct ControlCaps {
+    pub control_type: ASIControlType,
+    pub name: String,
+    pub description: String,
+    pub min: i64,
+    pub max: i64,
+    pub default: i64,
+    pub is_auto_supported: bool,
+    pub is_writable: bool,
+}
+
+impl From<&ASIControlCaps> for ControlCaps {
+    fn from(cap: &ASIControlCaps) -> Self {
+        ControlCaps {
+            control_type: cap.id,
+            name: String::from_utf8_lossy(&cap.name)
+                .trim_end_matches('\0')
+                .to_string(),
+            description: String::from_utf8_lossy(&cap.description)
+                .trim_end_matches('\0')
+                .to_string(),
+            min: cap.min_value,
+            max: cap.max_value,
+            default: cap.default_value,
+            is_auto_supported: cap.is_auto_supported,
+            is_writable: cap.is_writable,
+        }
+    }
+}
+
 #[derive(Clone)]
 struct ASIRoiMode {
     width: i32,
@@ -1156,6 +1703,237 @@ struct ASIRoiMode {
 #[derive(Clone, PartialEq, PartialOrd, Eq)]
 struct ASICamId(i32);
 
+/// A handle to an in-progress video-streaming capture session started by
+/// [`CameraUnit_ASI::start_video_capture`].
+///
+/// Dropping the stream stops the worker thread and issues `ASIStopVideoCapture`.
+pub struct VideoStream {
+    stop: Arc<AtomicBool>,
+    dropped: Arc<AtomicUsize>,
+    handle: Option<JoinHandle<()>>,
+    receiver: Receiver<ImageData>,
+    _free_sender: Sender<Vec<u8>>,
+}
+
+impl VideoStream {
+    /// Block until the next frame is available, or the stream is stopped.
+    pub fn recv(&self) -> Result<ImageData, Error> {
+        self.receiver
+            .recv()
+            .map_err(|_| Error::Message("Video stream closed".to_owned()))
+    }
+
+    /// Number of frames dropped so far because the consumer fell behind and
+    /// the ready queue was full when a new frame arrived.
+    pub fn dropped_frames(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Stop the streaming session, joining the worker thread.
+    pub fn stop(mut self) {
+        self.stop_inner();
+    }
+
+    fn stop_inner(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+impl Drop for VideoStream {
+    fn drop(&mut self) {
+        self.stop_inner();
+    }
+}
+
+/// Where and how [`CapturePipeline`] writes each captured frame to disk.
+/// Mirrors the arguments of [`ImageData::save_fits`].
+#[derive(Clone)]
+pub struct PipelineConfig {
+    pub dir_prefix: PathBuf,
+    pub file_prefix: String,
+    pub progname: String,
+    pub compress: bool,
+    pub overwrite: bool,
+}
+
+/// What the acquisition thread does when the writer falls behind and the
+/// filled-frame queue is already at `pool_size`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackpressurePolicy {
+    /// Discard the oldest queued frame to make room for the new one.
+    DropOldest,
+    /// Block the next exposure until the writer catches up.
+    Block,
+}
+
+/// A running double-buffered capture-and-save pipeline: one thread repeatedly
+/// calls [`CameraUnit_ASI::capture_image`], a second drains the resulting
+/// frames and writes them out with [`ImageData::save_fits`], so a slow disk
+/// never stalls the next exposure.
+///
+/// The two threads are connected by a bounded `crossbeam-channel` queue of
+/// capacity `pool_size`; [`BackpressurePolicy`] governs what happens when the
+/// writer can't keep up. Dropping the pipeline (or calling [`Self::stop`])
+/// stops acquisition, lets the writer drain whatever is already queued, and
+/// joins both threads.
+pub struct CapturePipeline {
+    stop: Arc<AtomicBool>,
+    dropped: Arc<AtomicUsize>,
+    acq_handle: Option<JoinHandle<()>>,
+    writer_handle: Option<JoinHandle<()>>,
+}
+
+impl CapturePipeline {
+    /// Start acquiring and saving frames from `cam` until stopped.
+    pub fn new(
+        cam: Arc<CameraUnit_ASI>,
+        config: PipelineConfig,
+        pool_size: usize,
+        policy: BackpressurePolicy,
+    ) -> Self {
+        let (filled_tx, filled_rx) = bounded::<ImageData>(pool_size);
+        let stop = Arc::new(AtomicBool::new(false));
+        let dropped = Arc::new(AtomicUsize::new(0));
+
+        let stop_acq = stop.clone();
+        let dropped_acq = dropped.clone();
+        let filled_rx_acq = filled_rx.clone();
+        let acq_handle = std::thread::spawn(move || {
+            'outer: while !stop_acq.load(Ordering::Relaxed) {
+                let frame = match cam.capture_image() {
+                    Ok(frame) => frame,
+                    Err(e) => {
+                        warn!("Capture pipeline: acquisition failed: {}", e);
+                        continue;
+                    }
+                };
+                match policy {
+                    BackpressurePolicy::Block => {
+                        if filled_tx.send(frame).is_err() {
+                            break;
+                        }
+                    }
+                    BackpressurePolicy::DropOldest => {
+                        let mut frame = frame;
+                        loop {
+                            match filled_tx.try_send(frame) {
+                                Ok(()) => break,
+                                Err(crossbeam_channel::TrySendError::Full(f)) => {
+                                    frame = f;
+                                    if filled_rx_acq.try_recv().is_ok() {
+                                        dropped_acq.fetch_add(1, Ordering::Relaxed);
+                                    }
+                                }
+                                Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
+                                    break 'outer
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let stop_writer = stop.clone();
+        let writer_handle = std::thread::spawn(move || {
+            let save = |frame: &ImageData| {
+                if let Err(e) = frame.save_fits(
+                    &config.dir_prefix,
+                    &config.file_prefix,
+                    &config.progname,
+                    config.compress,
+                    config.overwrite,
+                ) {
+                    warn!("Capture pipeline: failed to save frame: {}", e);
+                }
+            };
+            loop {
+                match filled_rx.recv_timeout(Duration::from_millis(500)) {
+                    Ok(frame) => save(&frame),
+                    Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                        if stop_writer.load(Ordering::Relaxed) {
+                            break;
+                        }
+                    }
+                    Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            // Drain whatever was queued before the stop flag was noticed.
+            while let Ok(frame) = filled_rx.try_recv() {
+                save(&frame);
+            }
+        });
+
+        CapturePipeline {
+            stop,
+            dropped,
+            acq_handle: Some(acq_handle),
+            writer_handle: Some(writer_handle),
+        }
+    }
+
+    /// Number of frames discarded under [`BackpressurePolicy::DropOldest`].
+    pub fn dropped_frames(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Stop acquisition, let the writer drain in-flight frames, and join
+    /// both threads.
+    pub fn stop(mut self) {
+        self.stop_inner();
+    }
+
+    fn stop_inner(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.acq_handle.take() {
+            handle.join().ok();
+        }
+        if let Some(handle) = self.writer_handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+impl Drop for CapturePipeline {
+    fn drop(&mut self) {
+        self.stop_inner();
+    }
+}
+
+/// Map the SDK's `Flip` control value (`0`..`3`) to a [`FlipStatus`]. Any
+/// other value (the control hasn't been touched, or the SDK version
+/// returned something unexpected) is reported as [`FlipStatus::None`].
+fn flip_status_from_raw(val: i64) -> FlipStatus {
+    match val {
+        1 => FlipStatus::Horizontal,
+        2 => FlipStatus::Vertical,
+        3 => FlipStatus::Both,
+        _ => FlipStatus::None,
+    }
+}
+
+/// Inverse of [`flip_status_from_raw`], for writing the `Flip` control.
+fn flip_status_to_raw(flip: FlipStatus) -> i64 {
+    match flip {
+        FlipStatus::None => 0,
+        FlipStatus::Horizontal => 1,
+        FlipStatus::Vertical => 2,
+        FlipStatus::Both => 3,
+    }
+}
+
+fn video_frame_size(roi: &ASIRoiMode) -> usize {
+    let pixels = (roi.width * roi.height) as usize;
+    match roi.fmt {
+        ASIImageFormat::Image_RAW8 => pixels,
+        ASIImageFormat::Image_RAW16 => pixels * 2,
+        ASIImageFormat::Image_RGB24 => pixels * 3,
+    }
+}
+
 impl Drop for ASICamId {
     fn drop(&mut self) {
         let res = unsafe { ASIStopExposure(self.0) };
@@ -1246,6 +2024,31 @@ fn sys_cancel_capture(id: i32) -> Result<(), Error> {
     Ok(())
 }
 
+/// Read the camera's current ROI dimensions, binning and pixel format.
+/// Shared by [`CameraInfo_ASI`] and [`CameraUnit_ASI`] since both need to
+/// report the live image format.
+fn get_roi_format(id: i32) -> Result<ASIRoiMode, Error> {
+    let mut roi = ASIRoiMode {
+        width: 0,
+        height: 0,
+        bin: 0,
+        fmt: ASIImageFormat::Image_RAW8,
+    };
+    let mut fmt: i32 = 0;
+    let res = unsafe { ASIGetROIFormat(id, &mut roi.width, &mut roi.height, &mut roi.bin, &mut fmt) };
+    if res == ASI_ERROR_CODE_ASI_ERROR_INVALID_ID as i32 {
+        return Err(Error::InvalidId(id));
+    } else if res == ASI_ERROR_CODE_ASI_ERROR_CAMERA_CLOSED as i32 {
+        return Err(Error::CameraClosed);
+    }
+    if let Some(fmt) = ASIImageFormat::from_u32(fmt as u32) {
+        roi.fmt = fmt;
+        Ok(roi)
+    } else {
+        Err(Error::InvalidMode(format!("Invalid image format: {}", fmt)))
+    }
+}
+
 fn get_control_value(id: i32, ctyp: ASIControlType) -> Result<(c_long, bool), Error> {
     let mut val: c_long = 0;
     let mut auto_val: i32 = ASI_BOOL_ASI_FALSE as i32;
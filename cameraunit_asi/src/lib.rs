@@ -1,8 +1,9 @@
 mod asicamera_2;
 
 pub use asicamera_2::{
-    get_camera_ids, num_cameras, open_camera, open_first_camera, ASICameraProps, ASIImageFormat,
-    CameraInfo_ASI, CameraUnit_ASI,
+    get_camera_ids, num_cameras, open_camera, open_first_camera, ASICameraProps, ASIControlType,
+    ASIImageFormat, BackpressurePolicy, CameraInfo_ASI, CameraUnit_ASI, CapturePipeline,
+    ControlCaps, PipelineConfig, ThermalStatus, VideoStream,
 };
 
 #[cfg(test)]
@@ -7,6 +7,27 @@ use std::fs::remove_file;
 use std::path::Path;
 use std::time::Duration;
 
+/// Bayer (CFA) mosaic pattern of the 2x2 tile at a sensor's pixel origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BayerPattern {
+    RGGB,
+    BGGR,
+    GRBG,
+    GBRG,
+}
+
+impl BayerPattern {
+    /// The FITS `BAYERPAT` keyword value for this pattern.
+    pub fn as_fits_str(&self) -> &'static str {
+        match self {
+            BayerPattern::RGGB => "RGGB",
+            BayerPattern::BGGR => "BGGR",
+            BayerPattern::GRBG => "GRBG",
+            BayerPattern::GBRG => "GBRG",
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ImageMetaData {
     pub bin_x: i32,
@@ -21,6 +42,9 @@ pub struct ImageMetaData {
     pub offset: i64,
     pub min_gain: i32,
     pub max_gain: i32,
+    /// Bayer pattern of the sensor that produced this frame, as reported at
+    /// capture time. `None` for monochrome cameras or already-debayered data.
+    pub bayer_pattern: Option<BayerPattern>,
     pub extended_metadata: Vec<(String, String)>,
 }
 
@@ -68,6 +92,61 @@ impl ImageMetaData {
     }
 }
 
+/// On-disk format for [`ImageData::save_image`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSaveFormat {
+    Png,
+    Fits,
+}
+
+/// How 16-bit pixel values are mapped into the 8-bit range by
+/// [`ImageData::preview`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stretch {
+    /// Linear stretch from the frame's own pixel min/max.
+    MinMax,
+    /// Linear stretch clipped to the given low/high percentile of pixel
+    /// values, e.g. `Percentile { low: 0.5, high: 99.5 }`, so a handful of
+    /// hot or saturated pixels don't wash out the rest of the range.
+    Percentile { low: f32, high: f32 },
+}
+
+/// cfitsio tile-compression algorithm for [`ImageData::save_fits_compressed`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FitsCompression {
+    /// Write an uncompressed image HDU.
+    None,
+    Rice,
+    Gzip,
+    Gzip2,
+    /// Lossy wavelet compression; `scale` controls the quantization level
+    /// (`0.0` is lossless, larger values compress more aggressively).
+    Hcompress { scale: f32 },
+}
+
+impl FitsCompression {
+    /// The cfitsio "extended filename syntax" directive to append to the
+    /// path passed to `FitsFile::create`, e.g. `[compress R 100,100]`.
+    /// Empty for [`FitsCompression::None`].
+    fn directive(&self, tile_dims: Option<(usize, usize)>) -> String {
+        let algo = match self {
+            FitsCompression::None => return String::new(),
+            FitsCompression::Rice => "R",
+            FitsCompression::Gzip => "G",
+            FitsCompression::Gzip2 => "G2",
+            FitsCompression::Hcompress { .. } => "H",
+        };
+        let tile = tile_dims
+            .map(|(tx, ty)| format!(" {},{}", tx, ty))
+            .unwrap_or_default();
+        let scale = match self {
+            FitsCompression::Hcompress { scale } => format!("; {}", scale),
+            _ => String::new(),
+        };
+        format!("[compress {}{}{}]", algo, tile, scale)
+    }
+}
+
 #[derive(Clone)]
 /// Image data structure
 ///
@@ -217,6 +296,9 @@ impl ImageData {
         Ok((target_exposure, bin))
     }
 
+    /// Convenience wrapper over [`Self::save_fits_compressed`] for callers
+    /// that only need the original on/off compression toggle: `true` maps to
+    /// [`FitsCompression::Rice`] with cfitsio's default tile size.
     pub fn save_fits(
         &self,
         dir_prefix: &Path,
@@ -224,6 +306,36 @@ impl ImageData {
         progname: &str,
         compress: bool,
         overwrite: bool,
+    ) -> Result<(), fitsio::errors::Error> {
+        let compression = if compress {
+            FitsCompression::Rice
+        } else {
+            FitsCompression::None
+        };
+        self.save_fits_compressed(
+            dir_prefix,
+            file_prefix,
+            progname,
+            compression,
+            None,
+            overwrite,
+        )
+    }
+
+    /// Write this frame to a FITS file, optionally using one of cfitsio's
+    /// tile-compression algorithms.
+    ///
+    /// `tile_dims`, if given, is passed through as the `<tilex>,<tiley>` tile
+    /// size in the compression directive; `None` lets cfitsio pick its own
+    /// default tile size.
+    pub fn save_fits_compressed(
+        &self,
+        dir_prefix: &Path,
+        file_prefix: &str,
+        progname: &str,
+        compression: FitsCompression,
+        tile_dims: Option<(usize, usize)>,
+        overwrite: bool,
     ) -> Result<(), fitsio::errors::Error> {
         if !dir_prefix.exists() {
             return Err(fitsio::errors::Error::Message(format!(
@@ -257,74 +369,634 @@ impl ImageData {
         }
 
         let imgtype = self.img.color();
-        let width = self.img.width();
-        let height = self.img.height();
-        let imgsize = [width as usize, height as usize];
-        let data_type: ImageType;
-
-        match imgtype {
-            image::ColorType::L8 => {
-                data_type = ImageType::UnsignedByte;
-            }
-            image::ColorType::L16 => {
-                data_type = ImageType::UnsignedShort;
-            }
-            _ => {
-                return Err(fitsio::errors::Error::Message(format!(
-                    "Unsupported image type {:?}",
-                    imgtype
-                )));
-            }
-        };
+        let (data_type, dimensions) = fits_layout(imgtype, self.img.width(), self.img.height())?;
 
         let img_desc = ImageDescription {
             data_type,
-            dimensions: &imgsize,
+            dimensions: &dimensions,
         };
         let path = Path::new(dir_prefix).join(Path::new(&format!(
             "{}_{}.fits{}",
             file_prefix,
             self.meta.timestamp,
-            if compress { "[compress]" } else { "" }
+            compression.directive(tile_dims)
         )));
         let mut fptr = FitsFile::create(path).open()?;
 
         let hdu = fptr.create_image("IMAGE".to_string(), &img_desc)?;
-        match imgtype {
-            image::ColorType::L8 => {
-                hdu.write_image(&mut fptr, self.img.to_luma8().as_raw())?;
+        write_image_payload(&hdu, &mut fptr, &self.img, imgtype)?;
+        hdu.write_key(&mut fptr, "PROGRAM", progname)?;
+        write_frame_keys(&hdu, &mut fptr, &self.meta, self.is_dark_frame(), &self.date_obs())?;
+
+        Ok(())
+    }
+
+    /// Demosaic this frame into RGB via bilinear interpolation over the 2x2
+    /// Bayer cell, using the pattern recorded in its metadata at capture
+    /// time. Preserves 16-bit depth for `L16` sources.
+    pub fn debayer(&self) -> Result<DynamicImage, String> {
+        let pattern = self
+            .meta
+            .bayer_pattern
+            .ok_or_else(|| "Frame has no recorded Bayer pattern".to_string())?;
+        Ok(debayer_bilinear(&self.img, pattern))
+    }
+
+    /// Down-sample this frame by box-averaging `scale x scale` blocks of
+    /// pixels, preserving bit depth and color type for 8/16-bit grayscale
+    /// and RGB frames (any other color type is converted to 8-bit
+    /// grayscale) and dropping any
+    /// trailing partial row/column that doesn't divide evenly. `bin_x`/
+    /// `bin_y` in the returned metadata are scaled to match. `scale` is
+    /// clamped to at least 1.
+    pub fn downscale(&self, scale: u32) -> ImageData {
+        let scale = scale.max(1);
+        let img = match &self.img {
+            DynamicImage::ImageLuma16(buf) => DynamicImage::from(box_average_luma16(buf, scale)),
+            DynamicImage::ImageLuma8(buf) => DynamicImage::from(box_average_luma8(buf, scale)),
+            DynamicImage::ImageRgb8(buf) => DynamicImage::from(box_average_rgb8(buf, scale)),
+            DynamicImage::ImageRgb16(buf) => DynamicImage::from(box_average_rgb16(buf, scale)),
+            _ => DynamicImage::from(box_average_luma8(&self.img.to_luma8(), scale)),
+        };
+        let mut meta = self.meta.clone();
+        meta.bin_x *= scale as i32;
+        meta.bin_y *= scale as i32;
+        ImageData::new(img, meta)
+    }
+
+    /// Down-sample this frame into an 8-bit thumbnail for quick on-screen
+    /// preview: blocks of `scale_factor x scale_factor` pixels are
+    /// box-averaged, and 16-bit grayscale data is mapped into the 8-bit
+    /// range using `stretch`. 8-bit RGB data keeps its color; any other
+    /// type (16-bit RGB included) is converted to 8-bit grayscale without
+    /// `stretch`. `scale_factor` is clamped to at least 1.
+    pub fn preview(&self, scale_factor: u32, stretch: Stretch) -> DynamicImage {
+        let scale = scale_factor.max(1);
+        match &self.img {
+            DynamicImage::ImageLuma16(buf) => {
+                let averaged = box_average_luma16(buf, scale);
+                let (lo, hi) = stretch_bounds(averaged.as_raw(), stretch);
+                DynamicImage::from(stretch_to_luma8(&averaged, lo, hi))
             }
-            image::ColorType::L16 => {
-                hdu.write_image(&mut fptr, self.img.to_luma16().as_raw())?;
+            DynamicImage::ImageRgb8(buf) => DynamicImage::from(box_average_rgb8(buf, scale)),
+            _ => DynamicImage::from(box_average_luma8(&self.img.to_luma8(), scale)),
+        }
+    }
+
+    /// `true` if the extended `DARK_FRAME` attribute recorded by the camera
+    /// backend is `"True"`.
+    fn is_dark_frame(&self) -> bool {
+        self.meta
+            .extended_metadata
+            .iter()
+            .any(|(k, v)| k == "DARK_FRAME" && v == "True")
+    }
+
+    /// FITS `DATE-OBS` formatted UTC timestamp derived from the metadata's
+    /// capture time.
+    fn date_obs(&self) -> String {
+        let secs = (self.meta.timestamp / 1000) as i64;
+        let millis = (self.meta.timestamp % 1000) as u32;
+        let dt = chrono::DateTime::<chrono::Utc>::from_timestamp(secs, millis * 1_000_000)
+            .unwrap_or_default();
+        dt.format("%Y-%m-%dT%H:%M:%S%.3f").to_string()
+    }
+
+    /// Write this frame as a PNG, choosing an 8- or 16-bit grayscale (or RGB)
+    /// color type to match the underlying buffer. 16-bit output is written
+    /// big-endian per the PNG spec by the `image` crate's encoder.
+    pub fn save_png(&self, path: &Path) -> Result<(), image::ImageError> {
+        self.img.save(path)
+    }
+
+    /// Persist this frame, picking the on-disk representation from `format`.
+    pub fn save_image(
+        &self,
+        dir_prefix: &Path,
+        file_prefix: &str,
+        progname: &str,
+        format: ImageSaveFormat,
+        compress: bool,
+        overwrite: bool,
+    ) -> Result<(), String> {
+        match format {
+            ImageSaveFormat::Fits => self
+                .save_fits(dir_prefix, file_prefix, progname, compress, overwrite)
+                .map_err(|e| e.to_string()),
+            ImageSaveFormat::Png => {
+                let path = dir_prefix.join(format!("{}_{}.png", file_prefix, self.meta.timestamp));
+                if path.exists() && !overwrite {
+                    return Err(format!("File {:?} already exists", path));
+                }
+                self.save_png(&path).map_err(|e| e.to_string())
             }
-            _ => {
+        }
+    }
+}
+
+/// The FITS pixel type and `NAXIS` dimensions for an image of `imgtype`,
+/// shared by [`ImageData::save_fits_compressed`] and [`SequenceWriter`].
+fn fits_layout(
+    imgtype: image::ColorType,
+    width: u32,
+    height: u32,
+) -> Result<(ImageType, Vec<usize>), fitsio::errors::Error> {
+    let (width, height) = (width as usize, height as usize);
+    match imgtype {
+        image::ColorType::L8 => Ok((ImageType::UnsignedByte, vec![width, height])),
+        image::ColorType::L16 => Ok((ImageType::UnsignedShort, vec![width, height])),
+        image::ColorType::Rgb8 => Ok((ImageType::UnsignedByte, vec![width, height, 3])),
+        image::ColorType::Rgb16 => Ok((ImageType::UnsignedShort, vec![width, height, 3])),
+        _ => Err(fitsio::errors::Error::Message(format!(
+            "Unsupported image type {:?}",
+            imgtype
+        ))),
+    }
+}
+
+/// Write `img`'s pixel data to `hdu`, matching the layout [`fits_layout`]
+/// computed for `imgtype`.
+fn write_image_payload(
+    hdu: &fitsio::hdu::FitsHdu,
+    fptr: &mut FitsFile,
+    img: &DynamicImage,
+    imgtype: image::ColorType,
+) -> Result<(), fitsio::errors::Error> {
+    match imgtype {
+        image::ColorType::L8 => hdu.write_image(fptr, img.to_luma8().as_raw()),
+        image::ColorType::L16 => hdu.write_image(fptr, img.to_luma16().as_raw()),
+        image::ColorType::Rgb8 => hdu.write_image(fptr, &planar_rgb8(&img.to_rgb8())),
+        image::ColorType::Rgb16 => hdu.write_image(fptr, &planar_rgb16(&img.to_rgb16())),
+        _ => Err(fitsio::errors::Error::Message(format!(
+            "Unsupported image type {:?}",
+            imgtype
+        ))),
+    }
+}
+
+/// Write this crate's metadata keys, plus the standard astronomy header
+/// cards, and any extended attributes, onto `hdu`.
+fn write_frame_keys(
+    hdu: &fitsio::hdu::FitsHdu,
+    fptr: &mut FitsFile,
+    meta: &ImageMetaData,
+    is_dark_frame: bool,
+    date_obs: &str,
+) -> Result<(), fitsio::errors::Error> {
+    hdu.write_key(fptr, "CAMERA", meta.camera_name.as_str())?;
+    hdu.write_key(fptr, "TIMESTAMP", meta.timestamp)?;
+    hdu.write_key(fptr, "CCDTEMP", meta.temperature)?;
+    hdu.write_key(fptr, "EXPOSURE_US", meta.exposure.as_micros() as u64)?;
+    hdu.write_key(fptr, "ORIGIN_X", meta.img_left)?;
+    hdu.write_key(fptr, "ORIGIN_Y", meta.img_top)?;
+    hdu.write_key(fptr, "BINX", meta.bin_x)?;
+    hdu.write_key(fptr, "BINY", meta.bin_y)?;
+    hdu.write_key(fptr, "GAIN", meta.gain)?;
+    hdu.write_key(fptr, "OFFSET", meta.offset)?;
+    hdu.write_key(fptr, "GAIN_MIN", meta.min_gain)?;
+    hdu.write_key(fptr, "GAIN_MAX", meta.max_gain)?;
+
+    // Standard astronomy header cards, in addition to this crate's own keys.
+    hdu.write_key(fptr, "EXPTIME", meta.exposure.as_secs_f64())?;
+    hdu.write_key(fptr, "CCD-TEMP", meta.temperature)?;
+    hdu.write_key(fptr, "XBINNING", meta.bin_x)?;
+    hdu.write_key(fptr, "YBINNING", meta.bin_y)?;
+    hdu.write_key(fptr, "XORGSUBF", meta.img_left)?;
+    hdu.write_key(fptr, "YORGSUBF", meta.img_top)?;
+    hdu.write_key(
+        fptr,
+        "IMAGETYP",
+        if is_dark_frame { "Dark Frame" } else { "Light Frame" },
+    )?;
+    hdu.write_key(fptr, "DATE-OBS", date_obs)?;
+    if let Some(pattern) = meta.bayer_pattern {
+        hdu.write_key(fptr, "BAYERPAT", pattern.as_fits_str())?;
+    }
+
+    for obj in meta.extended_metadata.iter() {
+        hdu.write_key(fptr, &obj.0, obj.1.as_str())?;
+    }
+
+    Ok(())
+}
+
+/// Writes a sequence of [`ImageData`] frames as successive HDUs in a single
+/// multi-extension FITS file: after the file's empty primary HDU, each frame
+/// is appended as its own `IMAGE<n>` extension, starting at `IMAGE0`. The
+/// `PROGRAM` key (shared across the whole sequence) is written once, on the
+/// primary HDU; every frame HDU gets its own frame's capture metadata via
+/// [`write_frame_keys`].
+///
+/// All appended frames must share the first frame's pixel type and
+/// dimensions, or [`Self::append`] fails without writing the mismatched HDU.
+pub struct SequenceWriter {
+    fptr: FitsFile,
+    progname: String,
+    layout: Option<(ImageType, Vec<usize>)>,
+    count: usize,
+}
+
+impl SequenceWriter {
+    /// Create a new, empty sequence file at `path`. `path`'s parent
+    /// directory must already exist; the file itself must not.
+    pub fn create(path: &Path, progname: &str) -> Result<Self, fitsio::errors::Error> {
+        let fptr = FitsFile::create(path).open()?;
+        Ok(SequenceWriter {
+            fptr,
+            progname: progname.to_string(),
+            layout: None,
+            count: 0,
+        })
+    }
+
+    /// Append `frame` as the next HDU in the sequence.
+    pub fn append(&mut self, frame: &ImageData) -> Result<(), fitsio::errors::Error> {
+        let imgtype = frame.img.color();
+        let layout = fits_layout(imgtype, frame.img.width(), frame.img.height())?;
+
+        match &self.layout {
+            Some(expected) if *expected != layout => {
                 return Err(fitsio::errors::Error::Message(format!(
-                    "Unsupported image type {:?}",
-                    imgtype
+                    "Frame {} has layout {:?}, expected {:?} to match the rest of the sequence",
+                    self.count, layout, expected
                 )));
             }
+            _ => {}
         }
-        hdu.write_key(&mut fptr, "PROGRAM", progname)?;
-        hdu.write_key(&mut fptr, "CAMERA", self.meta.camera_name.as_str())?;
-        hdu.write_key(&mut fptr, "TIMESTAMP", self.meta.timestamp)?;
-        hdu.write_key(&mut fptr, "CCDTEMP", self.meta.temperature)?;
-        hdu.write_key(
-            &mut fptr,
-            "EXPOSURE_US",
-            self.meta.exposure.as_micros() as u64,
-        )?;
-        hdu.write_key(&mut fptr, "ORIGIN_X", self.meta.img_left)?;
-        hdu.write_key(&mut fptr, "ORIGIN_Y", self.meta.img_top)?;
-        hdu.write_key(&mut fptr, "BINX", self.meta.bin_x)?;
-        hdu.write_key(&mut fptr, "BINY", self.meta.bin_y)?;
-        hdu.write_key(&mut fptr, "GAIN", self.meta.gain)?;
-        hdu.write_key(&mut fptr, "OFFSET", self.meta.offset)?;
-        hdu.write_key(&mut fptr, "GAIN_MIN", self.meta.min_gain)?;
-        hdu.write_key(&mut fptr, "GAIN_MAX", self.meta.max_gain)?;
-        for obj in self.meta.extended_metadata.iter() {
-            hdu.write_key(&mut fptr, &obj.0, obj.1.as_str())?;
+        if self.layout.is_none() {
+            self.layout = Some(layout.clone());
         }
 
+        let (data_type, dimensions) = layout;
+        let img_desc = ImageDescription {
+            data_type,
+            dimensions: &dimensions,
+        };
+        let hdu = self
+            .fptr
+            .create_image(format!("IMAGE{}", self.count), &img_desc)?;
+        write_image_payload(&hdu, &mut self.fptr, &frame.img, imgtype)?;
+        if self.count == 0 {
+            let primary = self.fptr.primary_hdu()?;
+            primary.write_key(&mut self.fptr, "PROGRAM", self.progname.as_str())?;
+        }
+        write_frame_keys(
+            &hdu,
+            &mut self.fptr,
+            &frame.meta,
+            frame.is_dark_frame(),
+            &frame.date_obs(),
+        )?;
+
+        self.count += 1;
         Ok(())
     }
+
+    /// Number of frames appended so far.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// `true` if no frames have been appended yet.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Close the sequence file. Equivalent to dropping the writer; exists so
+    /// callers can make the end of a sequence explicit in their own code.
+    pub fn finalize(self) {
+        drop(self);
+    }
+}
+
+/// Rearrange an interleaved RGB8 buffer into a planar R/G/B cube (all of R,
+/// then all of G, then all of B), the layout standard FITS viewers expect
+/// for a `NAXIS=3` color image.
+fn planar_rgb8(img: &image::RgbImage) -> Vec<u8> {
+    let n = (img.width() * img.height()) as usize;
+    let mut out = vec![0u8; n * 3];
+    for (i, px) in img.pixels().enumerate() {
+        out[i] = px.0[0];
+        out[n + i] = px.0[1];
+        out[2 * n + i] = px.0[2];
+    }
+    out
+}
+
+/// Same as [`planar_rgb8`] but for 16-bit RGB buffers.
+fn planar_rgb16(img: &image::ImageBuffer<image::Rgb<u16>, Vec<u16>>) -> Vec<u16> {
+    let n = (img.width() * img.height()) as usize;
+    let mut out = vec![0u16; n * 3];
+    for (i, px) in img.pixels().enumerate() {
+        out[i] = px.0[0];
+        out[n + i] = px.0[1];
+        out[2 * n + i] = px.0[2];
+    }
+    out
+}
+
+/// Which of R(0)/G(1)/B(2) sits at `(x, y)` in the 2x2 CFA tile for `pattern`.
+fn bayer_channel_at(pattern: BayerPattern, x: u32, y: u32) -> u8 {
+    match (pattern, x % 2, y % 2) {
+        (BayerPattern::RGGB, 0, 0) => 0,
+        (BayerPattern::RGGB, 1, 0) => 1,
+        (BayerPattern::RGGB, 0, 1) => 1,
+        (BayerPattern::RGGB, 1, 1) => 2,
+        (BayerPattern::BGGR, 0, 0) => 2,
+        (BayerPattern::BGGR, 1, 0) => 1,
+        (BayerPattern::BGGR, 0, 1) => 1,
+        (BayerPattern::BGGR, 1, 1) => 0,
+        (BayerPattern::GRBG, 0, 0) => 1,
+        (BayerPattern::GRBG, 1, 0) => 0,
+        (BayerPattern::GRBG, 0, 1) => 2,
+        (BayerPattern::GRBG, 1, 1) => 1,
+        (BayerPattern::GBRG, 0, 0) => 1,
+        (BayerPattern::GBRG, 1, 0) => 2,
+        (BayerPattern::GBRG, 0, 1) => 0,
+        (BayerPattern::GBRG, 1, 1) => 1,
+        _ => unreachable!(),
+    }
+}
+
+/// Bilinearly interpolate the two missing channels at `(x, y)`, dropping
+/// out-of-range neighbors from the average at the image border.
+fn debayer_pixel(get: &dyn Fn(i64, i64) -> Option<u32>, x: i64, y: i64, pattern: BayerPattern) -> [u32; 3] {
+    let avg = |coords: &[(i64, i64)]| -> u32 {
+        let (sum, count) = coords
+            .iter()
+            .filter_map(|&(cx, cy)| get(cx, cy))
+            .fold((0u32, 0u32), |(sum, count), v| (sum + v, count + 1));
+        if count == 0 {
+            0
+        } else {
+            sum / count
+        }
+    };
+
+    let (ux, uy) = (x as u32, y as u32);
+    let native = bayer_channel_at(pattern, ux, uy);
+    let mut out = [0u32; 3];
+    out[native as usize] = get(x, y).unwrap_or(0);
+    if native == 1 {
+        let horiz_channel = bayer_channel_at(pattern, ux.wrapping_add(1), uy);
+        let vert_channel = bayer_channel_at(pattern, ux, uy.wrapping_add(1));
+        out[horiz_channel as usize] = avg(&[(x - 1, y), (x + 1, y)]);
+        out[vert_channel as usize] = avg(&[(x, y - 1), (x, y + 1)]);
+    } else {
+        out[1] = avg(&[(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]);
+        let other = 2 - native;
+        out[other as usize] = avg(&[(x - 1, y - 1), (x + 1, y - 1), (x - 1, y + 1), (x + 1, y + 1)]);
+    }
+    out
+}
+
+/// Demosaic a single-channel Bayer frame (8- or 16-bit) into RGB.
+fn debayer_bilinear(img: &DynamicImage, pattern: BayerPattern) -> DynamicImage {
+    match img {
+        DynamicImage::ImageLuma16(buf) => {
+            let (w, h) = (buf.width(), buf.height());
+            let get = |x: i64, y: i64| -> Option<u32> {
+                if x < 0 || y < 0 || x >= w as i64 || y >= h as i64 {
+                    None
+                } else {
+                    Some(buf.get_pixel(x as u32, y as u32).0[0] as u32)
+                }
+            };
+            let mut out = image::ImageBuffer::<image::Rgb<u16>, Vec<u16>>::new(w, h);
+            for y in 0..h {
+                for x in 0..w {
+                    let px = debayer_pixel(&get, x as i64, y as i64, pattern);
+                    out.put_pixel(x, y, image::Rgb([px[0] as u16, px[1] as u16, px[2] as u16]));
+                }
+            }
+            DynamicImage::from(out)
+        }
+        _ => {
+            let buf = img.to_luma8();
+            let (w, h) = (buf.width(), buf.height());
+            let get = |x: i64, y: i64| -> Option<u32> {
+                if x < 0 || y < 0 || x >= w as i64 || y >= h as i64 {
+                    None
+                } else {
+                    Some(buf.get_pixel(x as u32, y as u32).0[0] as u32)
+                }
+            };
+            let mut out = image::ImageBuffer::<image::Rgb<u8>, Vec<u8>>::new(w, h);
+            for y in 0..h {
+                for x in 0..w {
+                    let px = debayer_pixel(&get, x as i64, y as i64, pattern);
+                    out.put_pixel(x, y, image::Rgb([px[0] as u8, px[1] as u8, px[2] as u8]));
+                }
+            }
+            DynamicImage::from(out)
+        }
+    }
+}
+
+/// Box-average each `scale x scale` block of a 16-bit grayscale buffer,
+/// truncating any trailing partial row/column that doesn't divide evenly.
+fn box_average_luma16(
+    buf: &image::ImageBuffer<image::Luma<u16>, Vec<u16>>,
+    scale: u32,
+) -> image::ImageBuffer<image::Luma<u16>, Vec<u16>> {
+    let out_w = buf.width() / scale;
+    let out_h = buf.height() / scale;
+    let area = (scale * scale) as u32;
+    let mut out = image::ImageBuffer::<image::Luma<u16>, Vec<u16>>::new(out_w, out_h);
+    for oy in 0..out_h {
+        for ox in 0..out_w {
+            let mut sum: u32 = 0;
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    sum += buf.get_pixel(ox * scale + dx, oy * scale + dy).0[0] as u32;
+                }
+            }
+            out.put_pixel(ox, oy, image::Luma([(sum / area) as u16]));
+        }
+    }
+    out
+}
+
+/// Same as [`box_average_luma16`] but for 8-bit grayscale buffers.
+fn box_average_luma8(
+    buf: &image::ImageBuffer<image::Luma<u8>, Vec<u8>>,
+    scale: u32,
+) -> image::ImageBuffer<image::Luma<u8>, Vec<u8>> {
+    let out_w = buf.width() / scale;
+    let out_h = buf.height() / scale;
+    let area = (scale * scale) as u32;
+    let mut out = image::ImageBuffer::<image::Luma<u8>, Vec<u8>>::new(out_w, out_h);
+    for oy in 0..out_h {
+        for ox in 0..out_w {
+            let mut sum: u32 = 0;
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    sum += buf.get_pixel(ox * scale + dx, oy * scale + dy).0[0] as u32;
+                }
+            }
+            out.put_pixel(ox, oy, image::Luma([(sum / area) as u8]));
+        }
+    }
+    out
+}
+
+/// Same as [`box_average_luma16`] but for 8-bit RGB buffers.
+fn box_average_rgb8(
+    buf: &image::ImageBuffer<image::Rgb<u8>, Vec<u8>>,
+    scale: u32,
+) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+    let out_w = buf.width() / scale;
+    let out_h = buf.height() / scale;
+    let area = (scale * scale) as u32;
+    let mut out = image::ImageBuffer::<image::Rgb<u8>, Vec<u8>>::new(out_w, out_h);
+    for oy in 0..out_h {
+        for ox in 0..out_w {
+            let mut sum = [0u32; 3];
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    let px = buf.get_pixel(ox * scale + dx, oy * scale + dy).0;
+                    for c in 0..3 {
+                        sum[c] += px[c] as u32;
+                    }
+                }
+            }
+            out.put_pixel(
+                ox,
+                oy,
+                image::Rgb([
+                    (sum[0] / area) as u8,
+                    (sum[1] / area) as u8,
+                    (sum[2] / area) as u8,
+                ]),
+            );
+        }
+    }
+    out
+}
+
+/// Same as [`box_average_luma16`] but for 16-bit RGB buffers.
+fn box_average_rgb16(
+    buf: &image::ImageBuffer<image::Rgb<u16>, Vec<u16>>,
+    scale: u32,
+) -> image::ImageBuffer<image::Rgb<u16>, Vec<u16>> {
+    let out_w = buf.width() / scale;
+    let out_h = buf.height() / scale;
+    let area = (scale * scale) as u32;
+    let mut out = image::ImageBuffer::<image::Rgb<u16>, Vec<u16>>::new(out_w, out_h);
+    for oy in 0..out_h {
+        for ox in 0..out_w {
+            let mut sum = [0u32; 3];
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    let px = buf.get_pixel(ox * scale + dx, oy * scale + dy).0;
+                    for c in 0..3 {
+                        sum[c] += px[c] as u32;
+                    }
+                }
+            }
+            out.put_pixel(
+                ox,
+                oy,
+                image::Rgb([
+                    (sum[0] / area) as u16,
+                    (sum[1] / area) as u16,
+                    (sum[2] / area) as u16,
+                ]),
+            );
+        }
+    }
+    out
+}
+
+/// The (lo, hi) 16-bit pixel values that map to 0 and 255 respectively
+/// under `stretch`.
+fn stretch_bounds(pixels: &[u16], stretch: Stretch) -> (u16, u16) {
+    match stretch {
+        Stretch::MinMax => {
+            let lo = *pixels.iter().min().unwrap_or(&0);
+            let hi = *pixels.iter().max().unwrap_or(&u16::MAX);
+            (lo, hi)
+        }
+        Stretch::Percentile { low, high } => {
+            let mut sorted = pixels.to_vec();
+            sorted.sort();
+            (percentile_value(&sorted, low), percentile_value(&sorted, high))
+        }
+    }
+}
+
+/// Pixel value at the given percentile (0-100) of an already-sorted slice.
+/// Mirrors the percentile-to-index logic in [`ImageData::find_optimum_exposure`].
+fn percentile_value(sorted: &[u16], pct: f32) -> u16 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let idx = if pct >= 100.0 {
+        sorted.len() - 1
+    } else if pct <= 0.0 {
+        0
+    } else {
+        ((pct * 0.01) * (sorted.len() - 1) as f32).floor() as usize
+    };
+    sorted[idx]
+}
+
+/// Linearly map each 16-bit pixel to 8-bit, clamping to `[lo, hi]` first.
+fn stretch_to_luma8(
+    img: &image::ImageBuffer<image::Luma<u16>, Vec<u16>>,
+    lo: u16,
+    hi: u16,
+) -> image::ImageBuffer<image::Luma<u8>, Vec<u8>> {
+    let span = (hi as f32 - lo as f32).max(1.0);
+    image::ImageBuffer::from_fn(img.width(), img.height(), |x, y| {
+        let v = img.get_pixel(x, y).0[0].clamp(lo, hi);
+        image::Luma([(((v as f32 - lo as f32) / span) * 255.0).round() as u8])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directive_emits_cfitsio_algorithm_tokens() {
+        assert_eq!(FitsCompression::None.directive(None), "");
+        assert_eq!(FitsCompression::Rice.directive(None), "[compress R]");
+        assert_eq!(FitsCompression::Gzip.directive(None), "[compress G]");
+        assert_eq!(FitsCompression::Gzip2.directive(None), "[compress G2]");
+        assert_eq!(
+            FitsCompression::Hcompress { scale: 4.0 }.directive(Some((100, 100))),
+            "[compress H 100,100; 4]"
+        );
+    }
+
+    #[test]
+    fn bayer_channel_at_rggb_tile() {
+        assert_eq!(bayer_channel_at(BayerPattern::RGGB, 0, 0), 0);
+        assert_eq!(bayer_channel_at(BayerPattern::RGGB, 1, 0), 1);
+        assert_eq!(bayer_channel_at(BayerPattern::RGGB, 0, 1), 1);
+        assert_eq!(bayer_channel_at(BayerPattern::RGGB, 1, 1), 2);
+    }
+
+    #[test]
+    fn debayer_bilinear_recovers_flat_color_from_rggb() {
+        // A uniformly-lit RGGB sensor should debayer back to a flat image
+        // whose R/G/B values match the mosaic's per-channel levels, since
+        // bilinear interpolation of a constant signal is that same constant.
+        let (r, g, b) = (200u8, 100u8, 50u8);
+        let mosaic = image::ImageBuffer::from_fn(4, 4, |x, y| {
+            image::Luma([match (x % 2, y % 2) {
+                (0, 0) => r,
+                (1, 0) | (0, 1) => g,
+                _ => b,
+            }])
+        });
+        let out = debayer_bilinear(&DynamicImage::from(mosaic), BayerPattern::RGGB);
+        let rgb = out.as_rgb8().unwrap();
+        for y in 0..4 {
+            for x in 0..4 {
+                assert_eq!(rgb.get_pixel(x, y).0, [r, g, b]);
+            }
+        }
+    }
 }
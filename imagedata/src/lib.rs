@@ -1,6 +1,9 @@
 mod imagedata;
 
-pub use imagedata::{ImageData, ImageMetaData};
+pub use imagedata::{
+    BayerPattern, FitsCompression, ImageData, ImageMetaData, ImageSaveFormat, SequenceWriter,
+    Stretch,
+};
 
 #[cfg(test)]
 mod tests {